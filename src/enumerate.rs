@@ -0,0 +1,170 @@
+//! Browse network resources (domains, servers, shares) visible to the current machine, built on
+//! `WNetOpenEnumA`, `WNetEnumResourceA`, and `WNetCloseEnum`.
+
+use crate::error::{Error, Result};
+use crate::SmbShare;
+use std::ffi::{CStr, CString};
+use tracing::{debug, trace};
+use windows_sys::Win32::Foundation::{
+    ERROR_EXTENDED_ERROR, ERROR_INVALID_PARAMETER, ERROR_MORE_DATA, ERROR_NO_MORE_ITEMS,
+    ERROR_NO_NETWORK, HANDLE, NO_ERROR,
+};
+use windows_sys::Win32::NetworkManagement::WNet;
+
+/// What kind of network object a [`NetworkResource`] represents, mirrored from `dwDisplayType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayType {
+    Domain,
+    Server,
+    Share,
+    Generic,
+}
+
+impl DisplayType {
+    fn from_raw(raw: u32) -> Self {
+        match raw {
+            WNet::RESOURCEDISPLAYTYPE_DOMAIN => Self::Domain,
+            WNet::RESOURCEDISPLAYTYPE_SERVER => Self::Server,
+            WNet::RESOURCEDISPLAYTYPE_SHARE => Self::Share,
+            _ => Self::Generic,
+        }
+    }
+}
+
+/// A single network object discovered while enumerating, e.g. a domain, server, or share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkResource {
+    pub remote_name: String,
+    pub comment: String,
+    pub display_type: DisplayType,
+    pub usage: u32,
+}
+
+impl SmbShare {
+    /// List the shares exposed by `server`, e.g. `"myserver"` for `\\myserver`.
+    ///
+    /// Useful to let a user browse a host before mapping one of its shares with [`SmbShare::new`]
+    /// and [`SmbShare::connect`].
+    ///
+    /// # Errors
+    /// This method will error if Windows is unable to enumerate the resources on `server`.
+    pub fn list_shares(server: &str) -> Result<Vec<NetworkResource>> {
+        let remote_name =
+            CString::new(format!(r"\\{server}")).map_err(|_| Error::NulInString)?;
+
+        let mut seed = WNet::NETRESOURCEA {
+            dwScope: WNet::RESOURCE_GLOBALNET,
+            dwType: WNet::RESOURCETYPE_DISK,
+            dwDisplayType: 0,
+            dwUsage: WNet::RESOURCEUSAGE_CONTAINER,
+            lpLocalName: std::ptr::null_mut(),
+            lpRemoteName: remote_name.as_c_str().as_ptr() as *mut u8,
+            lpComment: std::ptr::null_mut(),
+            lpProvider: std::ptr::null_mut(),
+        };
+
+        enumerate(Some(std::ptr::from_mut(&mut seed)))
+    }
+}
+
+/// List the servers (and domains) visible on the network.
+///
+/// # Errors
+/// This function will error if Windows is unable to enumerate the global network.
+pub fn list_servers() -> Result<Vec<NetworkResource>> {
+    enumerate(None)
+}
+
+/// Drive a full `WNetOpenEnumA`/`WNetEnumResourceA`/`WNetCloseEnum` cycle, growing the buffer as
+/// requested until Windows reports `ERROR_NO_MORE_ITEMS`.
+fn enumerate(seed: Option<*mut WNet::NETRESOURCEA>) -> Result<Vec<NetworkResource>> {
+    let mut handle: HANDLE = std::ptr::null_mut();
+
+    let open_result = unsafe {
+        WNet::WNetOpenEnumA(
+            WNet::RESOURCE_GLOBALNET,
+            WNet::RESOURCETYPE_DISK,
+            WNet::RESOURCEUSAGE_CONTAINER,
+            seed.unwrap_or(std::ptr::null_mut()).cast_const(),
+            &mut handle,
+        )
+    };
+
+    debug!("WNetOpenEnumA result: {open_result:#?}");
+
+    match open_result {
+        NO_ERROR => {}
+        ERROR_EXTENDED_ERROR => return Err(crate::get_extended_error()),
+        ERROR_INVALID_PARAMETER => return Err(Error::InvalidParameter),
+        ERROR_NO_NETWORK => return Err(Error::NoNetwork),
+        _ => return Err(Error::Other),
+    }
+
+    let mut resources = Vec::new();
+    let mut buffer_size = 16 * 1024u32;
+    let entry_size = std::mem::size_of::<WNet::NETRESOURCEA>();
+    // Allocate as `NETRESOURCEA` elements (not `u8`) so the buffer is aligned for the
+    // pointer-containing struct Windows writes into it; a `Vec<u8>` cast to
+    // `*const NETRESOURCEA` would let `WNetEnumResourceA` hand back misaligned reads.
+    let mut buffer: Vec<WNet::NETRESOURCEA> =
+        vec![unsafe { std::mem::zeroed() }; buffer_size.div_ceil(entry_size as u32) as usize];
+
+    let enum_result = loop {
+        let mut count = u32::MAX;
+
+        let enum_resource_result = unsafe {
+            WNet::WNetEnumResourceA(
+                handle,
+                &mut count,
+                buffer.as_mut_ptr().cast(),
+                &mut buffer_size,
+            )
+        };
+
+        trace!("WNetEnumResourceA result: {enum_resource_result:#?}, count: {count}");
+
+        match enum_resource_result {
+            NO_ERROR => {
+                let entries = buffer.as_ptr().cast::<WNet::NETRESOURCEA>();
+                for i in 0..count as usize {
+                    let entry = unsafe { *entries.add(i) };
+                    resources.push(NetworkResource {
+                        remote_name: read_cstr(entry.lpRemoteName),
+                        comment: read_cstr(entry.lpComment),
+                        display_type: DisplayType::from_raw(entry.dwDisplayType),
+                        usage: entry.dwUsage,
+                    });
+                }
+            }
+            ERROR_NO_MORE_ITEMS => break Ok(()),
+            ERROR_MORE_DATA => {
+                buffer.resize(
+                    buffer_size.div_ceil(entry_size as u32) as usize,
+                    unsafe { std::mem::zeroed() },
+                );
+            }
+            ERROR_EXTENDED_ERROR => break Err(crate::get_extended_error()),
+            ERROR_INVALID_PARAMETER => break Err(Error::InvalidParameter),
+            ERROR_NO_NETWORK => break Err(Error::NoNetwork),
+            _ => break Err(Error::Other),
+        }
+    };
+
+    unsafe {
+        WNet::WNetCloseEnum(handle);
+    }
+
+    enum_result.map(|()| resources)
+}
+
+/// Read a nul-terminated ANSI string out of a `NETRESOURCEA` field, treating a null pointer as an
+/// empty string.
+fn read_cstr(ptr: *mut u8) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    unsafe { CStr::from_ptr(ptr.cast()) }
+        .to_string_lossy()
+        .into_owned()
+}
@@ -1,4 +1,3 @@
-use std::ffi::NulError;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -6,8 +5,8 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug, PartialEq)]
 pub enum Error {
-    #[error("Failed to convert share to CString")]
-    CStringConversion(#[from] NulError),
+    #[error("A provided share, server name, local device name, username, or password contains an embedded NUL character, which Windows network APIs cannot represent")]
+    NulInString,
 
     #[error("The caller does not have access to the network resource.")]
     AccessDenied,
@@ -45,10 +44,12 @@ pub enum Error {
         "The local device name has a remembered connection to another network resource. This error is returned if an entry for the device specified by lpLocalName member of the NETRESOURCE structure pointed to by the lpNetResource parameter specifies a value that is already in the user profile for a different connection than that specified in the lpNetResource parameter."
     )]
     DeviceAlreadyRemembered,
-    #[error(
-        "A network-specific error occurred. Call the WNetGetLastError function to obtain a description of the error."
-    )]
-    ExtendedError,
+    #[error("A network-specific error occurred (provider: {provider}, code {code}): {description}")]
+    ExtendedError {
+        code: u32,
+        provider: String,
+        description: String,
+    },
     #[error(
         "An attempt was made to access an invalid address. This error is returned if the dwFlags parameter specifies a value of CONNECT_REDIRECT, but the lpLocalName member of the NETRESOURCE structure pointed to by the lpNetResource parameter was unspecified."
     )]
@@ -75,6 +76,10 @@ pub enum Error {
     NotConnected,
     #[error("There are open files, and the fForce parameter is FALSE.")]
     OpenFiles,
+    #[error(
+        "The device is not currently connected, but it is a remembered connection. This is typically returned when a persistent connection has not yet been restored, or failed to restore, since the user logged on."
+    )]
+    ConnectionUnavailable,
     #[error("Unknown error.")]
     Other,
 }
@@ -1,6 +1,6 @@
 #![warn(clippy::pedantic)]
 
-//! A tiny wrapper around `WNetAddConnection2A` and `WNetCancelConnection2A`. The goal is to offer an ergonomic interface to connect to an SMB network share on Windows.
+//! A tiny wrapper around `WNetAddConnection2W` and `WNetCancelConnection2W`. The goal is to offer an ergonomic interface to connect to an SMB network share on Windows.
 //!
 //! Sam -> SMB -> Rust -> Samba is taken!? == sambrs
 //!
@@ -20,26 +20,109 @@
 //! }
 //! ```
 
+mod enumerate;
 mod error;
 
+pub use enumerate::{list_servers, DisplayType, NetworkResource};
 pub use error::{Error, Result};
-use std::ffi::CString;
+use std::ffi::CStr;
+use std::os::windows::ffi::OsStrExt;
 use tracing::{debug, error, trace};
 use windows_sys::Win32::Foundation::{
     ERROR_ACCESS_DENIED, ERROR_ALREADY_ASSIGNED, ERROR_BAD_DEVICE, ERROR_BAD_DEV_TYPE,
     ERROR_BAD_NET_NAME, ERROR_BAD_PROFILE, ERROR_BAD_PROVIDER, ERROR_BAD_USERNAME, ERROR_BUSY,
-    ERROR_CANCELLED, ERROR_CANNOT_OPEN_PROFILE, ERROR_DEVICE_ALREADY_REMEMBERED,
-    ERROR_DEVICE_IN_USE, ERROR_EXTENDED_ERROR, ERROR_INVALID_ADDRESS, ERROR_INVALID_PARAMETER,
-    ERROR_INVALID_PASSWORD, ERROR_LOGON_FAILURE, ERROR_NOT_CONNECTED, ERROR_NO_NETWORK,
-    ERROR_NO_NET_OR_BAD_PATH, ERROR_OPEN_FILES, FALSE, NO_ERROR, TRUE,
+    ERROR_CANCELLED, ERROR_CANNOT_OPEN_PROFILE, ERROR_CONNECTION_UNAVAIL,
+    ERROR_DEVICE_ALREADY_REMEMBERED, ERROR_DEVICE_IN_USE, ERROR_EXTENDED_ERROR,
+    ERROR_INVALID_ADDRESS, ERROR_INVALID_PARAMETER, ERROR_INVALID_PASSWORD, ERROR_LOGON_FAILURE,
+    ERROR_MORE_DATA, ERROR_NOT_CONNECTED, ERROR_NO_NETWORK, ERROR_NO_NET_OR_BAD_PATH,
+    ERROR_OPEN_FILES, FALSE, HWND, NO_ERROR, TRUE,
 };
 use windows_sys::Win32::NetworkManagement::WNet;
 
+/// Encode a Rust string as a nul-terminated UTF-16 buffer for the `*W` WNet APIs, rejecting
+/// embedded NUL characters the same way `CString::new` would.
+fn to_wide(s: &str) -> Result<Vec<u16>> {
+    if s.contains('\0') {
+        return Err(Error::NulInString);
+    }
+
+    Ok(std::ffi::OsStr::new(s)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect())
+}
+
+/// Fetch the provider-specific details behind an `ERROR_EXTENDED_ERROR` result via
+/// `WNetGetLastErrorA`.
+///
+/// Must be called immediately after the failing WNet call, since the thread-local last-error
+/// state it reads can be clobbered by any subsequent WNet call.
+pub(crate) fn get_extended_error() -> Error {
+    let mut code = 0u32;
+    let mut description_buf = vec![0u8; 256];
+    let mut provider_buf = vec![0u8; 256];
+
+    let get_last_error_result = unsafe {
+        WNet::WNetGetLastErrorA(
+            &mut code,
+            description_buf.as_mut_ptr(),
+            description_buf.len() as u32,
+            provider_buf.as_mut_ptr(),
+            provider_buf.len() as u32,
+        )
+    };
+
+    debug!("WNetGetLastErrorA result: {get_last_error_result:#?}");
+
+    if get_last_error_result != NO_ERROR {
+        return Error::ExtendedError {
+            code,
+            provider: String::new(),
+            description: String::new(),
+        };
+    }
+
+    let description = unsafe { CStr::from_ptr(description_buf.as_ptr().cast()) }
+        .to_string_lossy()
+        .into_owned();
+    let provider = unsafe { CStr::from_ptr(provider_buf.as_ptr().cast()) }
+        .to_string_lossy()
+        .into_owned();
+
+    Error::ExtendedError {
+        code,
+        provider,
+        description,
+    }
+}
+
+/// The kind of network resource an [`SmbShare`] connects to, mirrored in `NETRESOURCE.dwType`.
+///
+/// Defaults to `Disk`. Set via [`SmbShare::with_resource_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResourceType {
+    #[default]
+    Disk,
+    Print,
+    Any,
+}
+
+impl ResourceType {
+    fn as_raw(self) -> u32 {
+        match self {
+            Self::Disk => WNet::RESOURCETYPE_DISK,
+            Self::Print => WNet::RESOURCETYPE_PRINT,
+            Self::Any => WNet::RESOURCETYPE_ANY,
+        }
+    }
+}
+
 pub struct SmbShare {
     share: String,
     username: String,
     password: String,
-    mount_on: Option<char>,
+    mount_on: Option<String>,
+    resource_type: ResourceType,
 }
 
 impl SmbShare {
@@ -48,6 +131,9 @@ impl SmbShare {
     /// Optionally specify `mount_on` to map the SMB share to a local device. Otherwise it will be
     /// a deviceless connection. Case insensitive.
     ///
+    /// `username` and `password` may be left empty to mean "authenticate as the currently
+    /// logged-on Windows user" when connecting with [`SmbShare::connect_as_current_user`].
+    ///
     /// # Example
     ///
     /// ```
@@ -63,10 +149,45 @@ impl SmbShare {
             share: share.into(),
             username: username.into(),
             password: password.into(),
-            mount_on,
+            mount_on: mount_on.map(|ln| ln.to_string()),
+            resource_type: ResourceType::default(),
         }
     }
 
+    /// Set the kind of resource to connect to, e.g. [`ResourceType::Print`] to connect as a
+    /// network printer (`RESOURCETYPE_PRINT`) instead of the default disk share.
+    ///
+    /// For a printer, pair this with [`SmbShare::with_mount_on`] to target an LPT device like `"LPT1:"`, since a
+    /// printer's local name isn't a single drive letter.
+    #[must_use]
+    pub fn with_resource_type(mut self, resource_type: ResourceType) -> Self {
+        self.resource_type = resource_type;
+        self
+    }
+
+    /// Override the local device name given to [`SmbShare::new`], e.g. to target an LPT device
+    /// like `"LPT1:"` for a printer share instead of a drive letter.
+    #[must_use]
+    pub fn with_mount_on(mut self, mount_on: impl Into<String>) -> Self {
+        self.mount_on = Some(mount_on.into());
+        self
+    }
+
+    /// Format `mount_on` into the local device name Windows expects, appending a drive-letter
+    /// colon when `mount_on` is a bare drive letter (e.g. `"D"`), regardless of `resource_type` —
+    /// `with_resource_type(Any)` is still a disk mapping as far as the drive letter is concerned.
+    fn local_name(&self) -> Option<String> {
+        self.mount_on.as_ref().map(|mount_on| {
+            let is_bare_drive_letter =
+                mount_on.len() == 1 && mount_on.chars().next().is_some_and(char::is_alphabetic);
+            if is_bare_drive_letter {
+                format!("{mount_on}:")
+            } else {
+                mount_on.clone()
+            }
+        })
+    }
+
     /// Connect to the SMB share. Connecting multiple times works fine in deviceless mode but fails
     /// with a local mount point.
     ///
@@ -74,7 +195,7 @@ impl SmbShare {
     ///   if `mount_on` is `None`
     /// - `interactive` will prompt the user for a password instead of failing with `Error::InvalidPassword`
     ///
-    /// # Some excerpts from the [Microsoft docs](https://learn.microsoft.com/en-us/windows/win32/api/winnetwk/nf-winnetwk-wnetaddconnection2a)
+    /// # Some excerpts from the [Microsoft docs](https://learn.microsoft.com/en-us/windows/win32/api/winnetwk/nf-winnetwk-wnetaddconnection2w)
     ///
     /// `persist` (`CONNECT_UPDATE_PROFILE`): The network resource connection should be remembered. If this bit
     /// flag is set, the operating system automatically attempts to restore the connection when the
@@ -97,16 +218,11 @@ impl SmbShare {
     /// # Errors
     /// This method will error if Windows is unable to connect to the SMB share.
     pub fn connect(&self, persist: bool, interactive: bool) -> Result<()> {
-        let local_name = self
-            .mount_on
-            .map(|ln| format!("{ln}:"))
-            .map(CString::new)
-            .transpose()?;
+        let mut local_name = self.local_name().map(|ln| to_wide(&ln)).transpose()?;
 
-        let local_name = match local_name {
-            Some(ref cstring) => cstring.as_c_str().as_ptr() as *mut u8,
-            None => std::ptr::null_mut(),
-        };
+        let local_name_ptr = local_name
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |wide| wide.as_mut_ptr());
 
         let mut flags = 0u32;
 
@@ -122,33 +238,31 @@ impl SmbShare {
 
         debug!("Connection flags: {flags:#?}");
 
-        let share = CString::new(&*self.share)?;
-        let username = CString::new(&*self.username)?;
-        let password = CString::new(&*self.password)?;
-
-        // https://learn.microsoft.com/en-us/windows/win32/api/winnetwk/ns-winnetwk-netresourcea
-        let mut netresource = WNet::NETRESOURCEA {
-            dwDisplayType: 0, // ignored by WNetAddConnection2A
-            dwScope: 0,       // ignored by WNetAddConnection2A
-            dwType: WNet::RESOURCETYPE_DISK,
-            dwUsage: 0, // ignored by WNetAddConnection2A
-            lpLocalName: local_name,
-            lpRemoteName: share.as_c_str().as_ptr() as *mut u8,
-            lpComment: std::ptr::null_mut(), // ignored by WNetAddConnection2A
+        let mut share = to_wide(&self.share)?;
+        let username = to_wide(&self.username)?;
+        let password = to_wide(&self.password)?;
+
+        // https://learn.microsoft.com/en-us/windows/win32/api/winnetwk/ns-winnetwk-netresourcew
+        let mut netresource = WNet::NETRESOURCEW {
+            dwDisplayType: 0, // ignored by WNetAddConnection2W
+            dwScope: 0,       // ignored by WNetAddConnection2W
+            dwType: self.resource_type.as_raw(),
+            dwUsage: 0, // ignored by WNetAddConnection2W
+            lpLocalName: local_name_ptr,
+            lpRemoteName: share.as_mut_ptr(),
+            lpComment: std::ptr::null_mut(), // ignored by WNetAddConnection2W
             lpProvider: std::ptr::null_mut(), // Microsoft docs: You should set this member only if you know the network provider you want to use.
                                               // Otherwise, let the operating system determine which provider the network name maps to.
         };
 
         trace!("Trying to connect to {}", self.share);
 
-        // https://learn.microsoft.com/en-us/windows/win32/api/winnetwk/nf-winnetwk-wnetaddconnection2a
+        // https://learn.microsoft.com/en-us/windows/win32/api/winnetwk/nf-winnetwk-wnetaddconnection2w
         let connection_result = unsafe {
-            let username = username.as_ref().as_ptr();
-            let password = password.as_ref().as_ptr();
-            WNet::WNetAddConnection2A(
-                std::ptr::from_mut::<WNet::NETRESOURCEA>(&mut netresource),
-                password.cast::<u8>(),
-                username.cast::<u8>(),
+            WNet::WNetAddConnection2W(
+                std::ptr::from_mut::<WNet::NETRESOURCEW>(&mut netresource),
+                password.as_ptr(),
+                username.as_ptr(),
                 flags,
             )
         };
@@ -169,7 +283,7 @@ impl SmbShare {
             ERROR_CANCELLED => Err(Error::Cancelled),
             ERROR_CANNOT_OPEN_PROFILE => Err(Error::CannotOpenProfile),
             ERROR_DEVICE_ALREADY_REMEMBERED => Err(Error::DeviceAlreadyRemembered),
-            ERROR_EXTENDED_ERROR => Err(Error::ExtendedError),
+            ERROR_EXTENDED_ERROR => Err(get_extended_error()),
             ERROR_INVALID_ADDRESS => Err(Error::InvalidAddress),
             ERROR_INVALID_PARAMETER => Err(Error::InvalidParameter),
             ERROR_INVALID_PASSWORD => Err(Error::InvalidPassword),
@@ -189,6 +303,119 @@ impl SmbShare {
         connection_result
     }
 
+    /// Connect to the SMB share as the currently logged-on Windows user, built on
+    /// `WNetAddConnection3W`.
+    ///
+    /// Pass empty `username`/`password` to [`SmbShare::new`] to authenticate with the caller's
+    /// logon token. The common pattern is to try this first and only fall back to
+    /// [`SmbShare::connect`] with explicit credentials if it fails:
+    ///
+    /// ```no_run
+    /// let share = sambrs::SmbShare::new(r"\\server\share", "", "", Some('D'));
+    /// if share.connect_as_current_user(None, false).is_err() {
+    ///     let share = sambrs::SmbShare::new(r"\\server\share", "user", "pass", Some('D'));
+    ///     share.connect(false, false).expect("Failed to connect");
+    /// }
+    /// ```
+    ///
+    /// `owner` is the `HWND` (as an `isize`) of the window that should own any interactive
+    /// credential dialog Windows raises, so the prompt is modal to the caller's application
+    /// instead of appearing detached. Passing `Some` also sets `CONNECT_INTERACTIVE`; with `None`
+    /// the connection is attempted non-interactively, so Windows raises no credential dialog.
+    ///
+    /// `persist` has the same meaning as in [`SmbShare::connect`].
+    ///
+    /// # Errors
+    /// This method will error if Windows is unable to connect to the SMB share.
+    pub fn connect_as_current_user(&self, owner: Option<isize>, persist: bool) -> Result<()> {
+        let mut local_name = self.local_name().map(|ln| to_wide(&ln)).transpose()?;
+
+        let local_name_ptr = local_name
+            .as_mut()
+            .map_or(std::ptr::null_mut(), |wide| wide.as_mut_ptr());
+
+        let mut flags = 0u32;
+
+        if persist && self.mount_on.is_some() {
+            flags |= WNet::CONNECT_UPDATE_PROFILE;
+        } else {
+            flags |= WNet::CONNECT_TEMPORARY;
+        }
+
+        if owner.is_some() {
+            flags |= WNet::CONNECT_INTERACTIVE;
+        }
+
+        debug!("Connection flags: {flags:#?}");
+
+        let mut share = to_wide(&self.share)?;
+        let username = (!self.username.is_empty())
+            .then(|| to_wide(&self.username))
+            .transpose()?;
+        let password = (!self.password.is_empty())
+            .then(|| to_wide(&self.password))
+            .transpose()?;
+
+        let mut netresource = WNet::NETRESOURCEW {
+            dwDisplayType: 0, // ignored by WNetAddConnection3W
+            dwScope: 0,       // ignored by WNetAddConnection3W
+            dwType: self.resource_type.as_raw(),
+            dwUsage: 0, // ignored by WNetAddConnection3W
+            lpLocalName: local_name_ptr,
+            lpRemoteName: share.as_mut_ptr(),
+            lpComment: std::ptr::null_mut(), // ignored by WNetAddConnection3W
+            lpProvider: std::ptr::null_mut(),
+        };
+
+        trace!("Trying to connect to {} as the current user", self.share);
+
+        // https://learn.microsoft.com/en-us/windows/win32/api/winnetwk/nf-winnetwk-wnetaddconnection3w
+        let connection_result = unsafe {
+            WNet::WNetAddConnection3W(
+                owner.map_or(std::ptr::null_mut(), |h| h as HWND),
+                std::ptr::from_mut::<WNet::NETRESOURCEW>(&mut netresource),
+                password.as_ref().map_or(std::ptr::null(), |p| p.as_ptr()),
+                username.as_ref().map_or(std::ptr::null(), |u| u.as_ptr()),
+                flags,
+            )
+        };
+
+        debug!("Connection result: {connection_result:#?}");
+
+        let connection_result = match connection_result {
+            NO_ERROR => Ok(()),
+            ERROR_ACCESS_DENIED => Err(Error::AccessDenied),
+            ERROR_ALREADY_ASSIGNED => Err(Error::AlreadyAssigned),
+            ERROR_BAD_DEV_TYPE => Err(Error::BadDevType),
+            ERROR_BAD_DEVICE => Err(Error::BadDevice),
+            ERROR_BAD_NET_NAME => Err(Error::BadNetName),
+            ERROR_BAD_PROFILE => Err(Error::BadProfile),
+            ERROR_BAD_PROVIDER => Err(Error::BadProvider),
+            ERROR_BAD_USERNAME => Err(Error::BadUsername),
+            ERROR_BUSY => Err(Error::Busy),
+            ERROR_CANCELLED => Err(Error::Cancelled),
+            ERROR_CANNOT_OPEN_PROFILE => Err(Error::CannotOpenProfile),
+            ERROR_DEVICE_ALREADY_REMEMBERED => Err(Error::DeviceAlreadyRemembered),
+            ERROR_EXTENDED_ERROR => Err(get_extended_error()),
+            ERROR_INVALID_ADDRESS => Err(Error::InvalidAddress),
+            ERROR_INVALID_PARAMETER => Err(Error::InvalidParameter),
+            ERROR_INVALID_PASSWORD => Err(Error::InvalidPassword),
+            ERROR_LOGON_FAILURE => Err(Error::LogonFailure),
+            ERROR_NO_NET_OR_BAD_PATH => Err(Error::NoNetOrBadPath),
+            ERROR_NO_NETWORK => Err(Error::NoNetwork),
+            _ => Err(Error::Other),
+        };
+
+        match connection_result {
+            Ok(()) => {
+                trace!("Successfully connected as the current user");
+            }
+            Err(ref e) => error!("Connection as current user failed: {e}"),
+        };
+
+        connection_result
+    }
+
     /// Disconnect from the SMB share.
     ///
     /// `persist` (`CONNECT_UPDATE_PROFILE`): The system updates the user profile with the
@@ -203,13 +430,12 @@ impl SmbShare {
     /// # Errors
     /// This method will return an error if Windows is unable to disconnect from the smb share.
     pub fn disconnect(&self, persist: bool, force: bool) -> Result<()> {
-        let local_name = self
-            .mount_on
-            .map(|ln| format!("{ln}:"))
-            .map(CString::new)
-            .transpose()?;
+        let local_name = self.local_name().map(|ln| to_wide(&ln)).transpose()?;
 
-        let resource_to_disconnect = local_name.unwrap_or(CString::new(&*self.share)?);
+        let resource_to_disconnect = match local_name {
+            Some(wide) => wide,
+            None => to_wide(&self.share)?,
+        };
 
         let force = if force { TRUE } else { FALSE };
 
@@ -220,7 +446,11 @@ impl SmbShare {
         };
 
         let disconnect_result = unsafe {
-            WNet::WNetCancelConnection2A(resource_to_disconnect.as_ptr() as *mut u8, persist, force)
+            WNet::WNetCancelConnection2W(
+                resource_to_disconnect.as_ptr() as *mut u16,
+                persist,
+                force,
+            )
         };
 
         debug!("Disconnect result: {disconnect_result:#?}");
@@ -230,7 +460,7 @@ impl SmbShare {
             ERROR_BAD_PROFILE => Err(Error::BadProfile),
             ERROR_CANNOT_OPEN_PROFILE => Err(Error::CannotOpenProfile),
             ERROR_DEVICE_IN_USE => Err(Error::DeviceInUse),
-            ERROR_EXTENDED_ERROR => Err(Error::ExtendedError),
+            ERROR_EXTENDED_ERROR => Err(get_extended_error()),
             ERROR_NOT_CONNECTED => Err(Error::NotConnected),
             ERROR_OPEN_FILES => Err(Error::OpenFiles),
             _ => Err(Error::Other),
@@ -243,6 +473,59 @@ impl SmbShare {
 
         disconnect_result
     }
+
+    /// Resolve the UNC path a local drive letter is currently mapped to, mirroring `net use`.
+    ///
+    /// This is useful to verify a letter actually points at the share a caller expects before
+    /// using it or before forcing a disconnect, since some providers leave stale remembered
+    /// connections behind.
+    ///
+    /// # Errors
+    /// This method will return `Error::NotConnected` if the letter has no connection at all, and
+    /// `Error::ConnectionUnavailable` if it is a remembered connection that has not been restored.
+    pub fn resolve_remote(local: char) -> Result<String> {
+        let mut local_name = to_wide(&format!("{local}:"))?;
+
+        let mut buffer_len = 260u32; // MAX_PATH, grown below if Windows asks for more.
+        let mut buffer = vec![0u16; buffer_len as usize];
+
+        loop {
+            let get_connection_result = unsafe {
+                WNet::WNetGetConnectionW(
+                    local_name.as_mut_ptr(),
+                    buffer.as_mut_ptr(),
+                    &mut buffer_len,
+                )
+            };
+
+            debug!("WNetGetConnectionW result: {get_connection_result:#?}");
+
+            match get_connection_result {
+                NO_ERROR => {
+                    let remote = buffer.split(|&c| c == 0).next().unwrap_or_default();
+                    return Ok(String::from_utf16_lossy(remote));
+                }
+                ERROR_MORE_DATA => buffer.resize(buffer_len as usize, 0),
+                ERROR_CONNECTION_UNAVAIL => return Err(Error::ConnectionUnavailable),
+                ERROR_NOT_CONNECTED => return Err(Error::NotConnected),
+                ERROR_BAD_DEVICE => return Err(Error::BadDevice),
+                _ => return Err(Error::Other),
+            }
+        }
+    }
+
+    /// Report whether `local` is currently connected to a network resource.
+    ///
+    /// # Errors
+    /// This method will return an error if Windows is unable to query the connection state, for
+    /// any reason other than the letter simply not being connected.
+    pub fn connection_status(local: char) -> Result<bool> {
+        match Self::resolve_remote(local) {
+            Ok(_) => Ok(true),
+            Err(Error::NotConnected | Error::ConnectionUnavailable) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -327,6 +610,37 @@ mod tests {
         assert!(disconnect.is_ok());
     }
 
+    #[test]
+    fn to_wide_rejects_interior_nul() {
+        assert_eq!(to_wide("sh\0are"), Err(Error::NulInString));
+    }
+
+    #[test]
+    fn to_wide_null_terminates_the_encoded_buffer() {
+        let expected: Vec<u16> = "share".encode_utf16().chain(std::iter::once(0)).collect();
+        assert_eq!(to_wide("share").unwrap(), expected);
+    }
+
+    #[test]
+    fn local_name_appends_colon_to_bare_drive_letter() {
+        let share = SmbShare::new(VALID_SHARE, CORRECT_USERNAME, CORRECT_PASSWORD, Some('s'));
+        assert_eq!(share.local_name(), Some("s:".to_string()));
+    }
+
+    #[test]
+    fn local_name_passes_through_non_drive_letter_mount_on() {
+        let share = SmbShare::new(VALID_SHARE, CORRECT_USERNAME, CORRECT_PASSWORD, None)
+            .with_mount_on("LPT1:");
+        assert_eq!(share.local_name(), Some("LPT1:".to_string()));
+    }
+
+    #[test]
+    fn local_name_does_not_double_up_an_existing_colon() {
+        let share = SmbShare::new(VALID_SHARE, CORRECT_USERNAME, CORRECT_PASSWORD, None)
+            .with_mount_on("s:");
+        assert_eq!(share.local_name(), Some("s:".to_string()));
+    }
+
     #[test]
     fn happy_connecting_multiple_letters_to_same_share_works() {
         let share_one = SmbShare::new(VALID_SHARE, CORRECT_USERNAME, CORRECT_PASSWORD, Some('s'));